@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use little_schemer::lexer::lex_input;
+
+const FIZZBUZZ: &str = r#"
+(define (fizzbuzz num)
+  (let ((isFizzable (= 0 (modulo num 3)))
+        (isBuzzable (= 0 (modulo num 5))))
+    (cond
+      ((and isFizzable isBuzzable) "fizzbuzz")
+      (isFizzable "fizz")
+      (isBuzzable "buzz")
+      (#t (number->string num)))))
+"#;
+
+fn lex_benchmarks(c: &mut Criterion) {
+    let benchmarks = vec![
+        ("large file", FIZZBUZZ.repeat(2000)),
+        (
+            "deeply nested list",
+            "(".repeat(50_000) + &")".repeat(50_000),
+        ),
+        (
+            "long string",
+            format!("\"{}\"", "little \\\"schemer\\\" λ ".repeat(20_000)),
+        ),
+    ];
+
+    let mut group = c.benchmark_group("lex_input");
+
+    for (name, input) in benchmarks {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &input, |b, input| {
+            b.iter(|| lex_input(input).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, lex_benchmarks);
+criterion_main!(benches);
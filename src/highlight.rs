@@ -0,0 +1,260 @@
+use crate::lexer::{self, LexToken};
+
+/// How highlighted source is annotated.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Format {
+    /// Terminal colour escape codes.
+    Ansi,
+    /// `<span class="...">` elements, for embedding in a `<pre>` block.
+    Html,
+}
+
+/// The kind of source text a token is highlighted as.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Class {
+    Keyword,
+    Symbol,
+    Constant,
+    String,
+    Punctuation,
+    Comment,
+}
+
+const KEYWORDS: &[&str] = &[
+    "and",
+    "begin",
+    "case",
+    "cond",
+    "define",
+    "define-syntax",
+    "do",
+    "else",
+    "if",
+    "lambda",
+    "let",
+    "let*",
+    "letrec",
+    "or",
+    "quasiquote",
+    "quote",
+    "set!",
+    "syntax-rules",
+    "unless",
+    "when",
+];
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Annotates the input with the class of each token it lexes to. Text
+/// between tokens, such as whitespace and directives, is left as it is.
+///
+/// Input that can't be lexed, e.g. a string still waiting for its closing
+/// quote, is returned without any highlighting.
+pub fn highlight(input: &str, fold_case: bool, format: Format) -> String {
+    let tokens = match lexer::lex_input_with_ranges(input, fold_case) {
+        Ok(tokens) => tokens,
+        Err(_) => return escape(input, format),
+    };
+
+    let mut output = String::with_capacity(input.len());
+    let mut last_idx = 0;
+    let mut comment = DatumComment::default();
+
+    for (token, range) in tokens {
+        output.push_str(&escape(&input[last_idx..range.start], format));
+
+        let text = escape(&input[range.clone()], format);
+        let class = if comment.covers(&token) {
+            Class::Comment
+        } else {
+            class_of(&token)
+        };
+
+        match format {
+            Format::Ansi => match ansi_colour(class) {
+                Some(colour) => {
+                    output.push_str(colour);
+                    output.push_str(&text);
+                    output.push_str(ANSI_RESET);
+                }
+                None => output.push_str(&text),
+            },
+            Format::Html => {
+                output.push_str(&format!(
+                    "<span class=\"{}\">{}</span>",
+                    html_class(class),
+                    text
+                ));
+            }
+        }
+
+        last_idx = range.end;
+    }
+
+    output.push_str(&escape(&input[last_idx..], format));
+
+    output
+}
+
+/// Tracks the datums commented out by `#;`, so the whole datum can be
+/// highlighted as a comment rather than just the `#;` itself.
+#[derive(Default)]
+struct DatumComment {
+    datums_remaining: usize,
+    depth: usize,
+}
+
+impl DatumComment {
+    /// Returns whether the token is part of a comment, moving past it.
+    fn covers(&mut self, token: &LexToken) -> bool {
+        match token {
+            LexToken::DatumComment => {
+                if self.depth == 0 {
+                    self.datums_remaining += 1;
+                }
+                return true;
+            }
+            // A closing bracket with no datum left to comment out belongs
+            // to the enclosing list, e.g. in `(a #;)`.
+            LexToken::RightBracket if self.depth == 0 => {
+                self.datums_remaining = 0;
+                return false;
+            }
+            _ if self.datums_remaining == 0 => return false,
+            LexToken::LeftBracket | LexToken::VectorStart | LexToken::BytevectorStart => {
+                self.depth += 1
+            }
+            LexToken::RightBracket => self.depth -= 1,
+            _ => {}
+        }
+
+        if self.depth == 0 && *token != LexToken::Quote {
+            self.datums_remaining -= 1;
+        }
+
+        true
+    }
+}
+
+fn class_of(token: &LexToken) -> Class {
+    match token {
+        LexToken::Symbol(name) if KEYWORDS.contains(&name.as_str()) => Class::Keyword,
+        LexToken::Symbol(_) => Class::Symbol,
+        LexToken::Num(_) | LexToken::Bool(_) | LexToken::Char(_) => Class::Constant,
+        LexToken::String(_) => Class::String,
+        LexToken::DatumComment => Class::Comment,
+        LexToken::LeftBracket
+        | LexToken::RightBracket
+        | LexToken::Dot
+        | LexToken::Quote
+        | LexToken::VectorStart
+        | LexToken::BytevectorStart => Class::Punctuation,
+    }
+}
+
+/// Symbols and punctuation keep the terminal's default colour.
+fn ansi_colour(class: Class) -> Option<&'static str> {
+    match class {
+        Class::Keyword => Some("\x1b[1;35m"),
+        Class::Constant => Some("\x1b[36m"),
+        Class::String => Some("\x1b[32m"),
+        Class::Comment => Some("\x1b[2m"),
+        Class::Symbol | Class::Punctuation => None,
+    }
+}
+
+fn html_class(class: Class) -> &'static str {
+    match class {
+        Class::Keyword => "keyword",
+        Class::Symbol => "symbol",
+        Class::Constant => "constant",
+        Class::String => "string",
+        Class::Punctuation => "punctuation",
+        Class::Comment => "comment",
+    }
+}
+
+fn escape(text: &str, format: Format) -> String {
+    match format {
+        Format::Ansi => text.to_string(),
+        Format::Html => text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_html() {
+        let input = "(define x \"<a>\") #;#\\a";
+
+        let expected_output = concat!(
+            "<span class=\"punctuation\">(</span>",
+            "<span class=\"keyword\">define</span> ",
+            "<span class=\"symbol\">x</span> ",
+            "<span class=\"string\">&quot;&lt;a&gt;&quot;</span>",
+            "<span class=\"punctuation\">)</span> ",
+            "<span class=\"comment\">#;</span>",
+            "<span class=\"comment\">#\\a</span>",
+        );
+
+        assert_eq!(highlight(input, false, Format::Html), expected_output);
+    }
+
+    #[test]
+    fn highlight_ansi() {
+        let input = "#!fold-case\n(IF #t 1)";
+
+        let expected_output = concat!(
+            "#!fold-case\n(",
+            "\x1b[1;35mIF\x1b[0m ",
+            "\x1b[36m#t\x1b[0m ",
+            "\x1b[36m1\x1b[0m)",
+        );
+
+        assert_eq!(highlight(input, false, Format::Ansi), expected_output);
+    }
+
+    #[test]
+    fn highlight_datum_comments() {
+        let input = "(a #;(b 'c) #;#;1 2 d #;)";
+
+        let expected_output = [
+            "(a ",
+            &comment("#;"),
+            &comment("("),
+            &comment("b"),
+            " ",
+            &comment("'"),
+            &comment("c"),
+            &comment(")"),
+            " ",
+            &comment("#;"),
+            &comment("#;"),
+            &comment("1"),
+            " ",
+            &comment("2"),
+            " d ",
+            &comment("#;"),
+            ")",
+        ]
+        .concat();
+
+        assert_eq!(highlight(input, false, Format::Ansi), expected_output);
+    }
+
+    #[test]
+    fn highlight_unlexable_input() {
+        assert_eq!(highlight("(a \"b", false, Format::Ansi), "(a \"b");
+        assert_eq!(highlight("(\"<b", false, Format::Html), "(&quot;&lt;b");
+    }
+
+    fn comment(text: &str) -> String {
+        format!("\x1b[2m{}\x1b[0m", text)
+    }
+}
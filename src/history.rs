@@ -0,0 +1,58 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Where REPL history is kept: `$XDG_STATE_HOME/littleschemer/history`
+/// when XDG_STATE_HOME is set, otherwise `~/.littleschemer_history`.
+pub fn history_path() -> Option<PathBuf> {
+    history_path_from(env::var_os("XDG_STATE_HOME"), env::var_os("HOME"))
+}
+
+fn history_path_from(
+    xdg_state_home: Option<std::ffi::OsString>,
+    home: Option<std::ffi::OsString>,
+) -> Option<PathBuf> {
+    // The XDG spec says relative paths are invalid and should be ignored.
+    if let Some(xdg_state_home) = xdg_state_home.map(PathBuf::from) {
+        if xdg_state_home.is_absolute() {
+            return Some(xdg_state_home.join("littleschemer").join("history"));
+        }
+    }
+
+    let home = PathBuf::from(home?);
+
+    Some(home.join(".littleschemer_history"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn history_path_prefers_xdg_state_home() {
+        let tests = vec![
+            (
+                Some("/state"),
+                Some("/home/user"),
+                Some("/state/littleschemer/history"),
+            ),
+            (
+                Some("relative"),
+                Some("/home/user"),
+                Some("/home/user/.littleschemer_history"),
+            ),
+            (
+                None,
+                Some("/home/user"),
+                Some("/home/user/.littleschemer_history"),
+            ),
+            (None, None, None),
+        ];
+
+        for (xdg_state_home, home, expect) in tests {
+            let actual_output =
+                history_path_from(xdg_state_home.map(Into::into), home.map(Into::into));
+
+            assert_eq!(actual_output, expect.map(PathBuf::from));
+        }
+    }
+}
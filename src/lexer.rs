@@ -1,94 +1,247 @@
-#[derive(Debug, PartialEq)]
+use std::ops::Range;
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum LexToken {
     Num(f64),
+    Bool(bool),
     Symbol(String),
     String(String),
     LeftBracket,
     RightBracket,
+    Dot,
+    Quote,
+    VectorStart,
+    BytevectorStart,
+    Char(char),
+    DatumComment,
+}
+
+/// A 1-based line and column in the lexer input.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
 }
 
+pub const MISSING_CLOSING_QUOTE: &str = "Missing closing quote in string";
+pub const MISSING_CLOSING_PIPE: &str = "Missing closing | in symbol";
+
+/// Walks the input by byte offset, so lookahead never has to re-walk the
+/// input from the start. `skip` must only be given the byte length of
+/// characters already matched, so `current_idx` always lands on a UTF-8
+/// boundary; `take_next` skips a character of any width.
 struct InputBuffer<'a> {
     input: &'a str,
     current_idx: usize,
+    fold_case: bool,
+    position_idx: usize,
+    position: Position,
 }
 
-impl InputBuffer<'_> {
-    fn from_input(input: &str) -> InputBuffer {
+impl<'a> InputBuffer<'a> {
+    fn from_input(input: &str, fold_case: bool) -> InputBuffer<'_> {
         InputBuffer {
             input,
             current_idx: 0,
+            fold_case,
+            position_idx: 0,
+            position: Position { line: 1, column: 1 },
         }
     }
 
+    fn remaining(&self) -> &'a str {
+        &self.input[self.current_idx..]
+    }
+
     fn has_chars_remaining(&self) -> bool {
-        self.input.chars().count() > self.current_idx
+        self.input.len() > self.current_idx
+    }
+
+    /// Only scans the input consumed since the last call, so asking for
+    /// the position of every token stays linear overall.
+    fn position(&mut self) -> Position {
+        for char in self.input[self.position_idx..self.current_idx].chars() {
+            if char == '\n' {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else {
+                self.position.column += 1;
+            }
+        }
+
+        self.position_idx = self.current_idx;
+
+        self.position
     }
 
     fn next_char_is(&self, look_for: fn(char) -> bool) -> bool {
-        let next_char = self
-            .input
-            .chars()
-            .nth(self.current_idx)
-            .expect("Lexxer skipped past the end of the input");
+        match self.remaining().chars().next() {
+            Some(next_char) => look_for(next_char),
+            None => false,
+        }
+    }
 
-        look_for(next_char)
+    fn next_chars_are(&self, look_for: &str) -> bool {
+        self.remaining().starts_with(look_for)
     }
 
-    fn skip(&mut self, num_chars_to_skip: usize) {
-        self.current_idx += num_chars_to_skip;
+    fn skip(&mut self, num_bytes_to_skip: usize) {
+        self.current_idx += num_bytes_to_skip;
     }
 
-    fn take_while(&mut self, look_for: for<'r> fn(&'r char) -> bool) -> String {
+    fn take_while(&mut self, look_for: for<'r> fn(&'r char) -> bool) -> &'a str {
         let output = self.read_while(look_for);
 
-        self.skip(output.chars().count());
+        self.skip(output.len());
 
         output
     }
 
-    fn take_next(&mut self) -> char {
-        let output = self
-            .input
-            .chars()
-            .nth(self.current_idx)
-            .expect("Lexxer skipped past the end of the input");
+    fn take_next(&mut self) -> Option<char> {
+        let output = self.remaining().chars().next()?;
 
-        self.skip(1);
+        self.skip(output.len_utf8());
 
-        output
+        Some(output)
     }
 
-    fn read_while(&self, look_for: for<'r> fn(&'r char) -> bool) -> String {
-        self.input
-            .chars()
-            .skip(self.current_idx)
-            .take_while(look_for)
-            .collect::<String>()
+    fn read_while(&self, look_for: for<'r> fn(&'r char) -> bool) -> &'a str {
+        let remaining = self.remaining();
+
+        let end_idx = remaining
+            .char_indices()
+            .find(|(_, char)| !look_for(char))
+            .map_or(remaining.len(), |(idx, _)| idx);
+
+        &remaining[..end_idx]
     }
 }
 
 pub fn lex_input(input: &str) -> Result<Vec<LexToken>, &'static str> {
-    let mut input_buffer = InputBuffer::from_input(input);
+    lex_input_with_positions(input, false).map(without_positions)
+}
+
+/// Lexes as if the input started with `#!fold-case`, so symbols and
+/// character names are case insensitive until a `#!no-fold-case`.
+pub fn lex_input_folding_case(input: &str) -> Result<Vec<LexToken>, &'static str> {
+    lex_input_with_positions(input, true).map(without_positions)
+}
+
+/// Lexes the input, pairing each token with the position it starts at.
+pub fn lex_input_with_positions(
+    input: &str,
+    fold_case: bool,
+) -> Result<Vec<(LexToken, Position)>, &'static str> {
+    let output = lex(input, fold_case)?
+        .into_iter()
+        .map(|(token, position, _)| (token, position))
+        .collect();
+
+    Ok(output)
+}
+
+/// Lexes the input, pairing each token with the byte range of the source
+/// text it was read from.
+pub fn lex_input_with_ranges(
+    input: &str,
+    fold_case: bool,
+) -> Result<Vec<(LexToken, Range<usize>)>, &'static str> {
+    let output = lex(input, fold_case)?
+        .into_iter()
+        .map(|(token, _, range)| (token, range))
+        .collect();
+
+    Ok(output)
+}
+
+type LocatedToken = (LexToken, Position, Range<usize>);
+
+fn lex(input: &str, fold_case: bool) -> Result<Vec<LocatedToken>, &'static str> {
+    let mut input_buffer = InputBuffer::from_input(input, fold_case);
     let mut output = Vec::new();
 
+    skip_shebang(&mut input_buffer);
+
     while input_buffer.has_chars_remaining() {
-        if let Some(lexed_string) = lex_string(&mut input_buffer) {
-            output.push(lexed_string);
+        let position = input_buffer.position();
+        let start_idx = input_buffer.current_idx;
+
+        if let Some(lexed_string) = lex_string(&mut input_buffer)? {
+            output.push((lexed_string, position, start_idx..input_buffer.current_idx));
+            continue;
+        }
+
+        if let Some(lexed_pipe_symbol) = lex_pipe_symbol(&mut input_buffer)? {
+            output.push((
+                lexed_pipe_symbol,
+                position,
+                start_idx..input_buffer.current_idx,
+            ));
+            continue;
+        }
+
+        if let Some(lexed_dot) = lex_dot(&mut input_buffer) {
+            output.push((lexed_dot, position, start_idx..input_buffer.current_idx));
             continue;
         }
 
         if let Some(lexed_number) = lex_number(&mut input_buffer) {
-            output.push(lexed_number);
+            output.push((lexed_number, position, start_idx..input_buffer.current_idx));
+            continue;
+        }
+
+        if let Some(lexed_quote) = lex_quote(&mut input_buffer) {
+            output.push((lexed_quote, position, start_idx..input_buffer.current_idx));
+            continue;
+        }
+
+        if let Some(lexed_vector_start) = lex_vector_start(&mut input_buffer) {
+            output.push((
+                lexed_vector_start,
+                position,
+                start_idx..input_buffer.current_idx,
+            ));
+            continue;
+        }
+
+        if let Some(lexed_bytevector_start) = lex_bytevector_start(&mut input_buffer) {
+            output.push((
+                lexed_bytevector_start,
+                position,
+                start_idx..input_buffer.current_idx,
+            ));
+            continue;
+        }
+
+        if let Some(lexed_datum_comment) = lex_datum_comment(&mut input_buffer) {
+            output.push((
+                lexed_datum_comment,
+                position,
+                start_idx..input_buffer.current_idx,
+            ));
+            continue;
+        }
+
+        if let Some(lexed_char) = lex_char(&mut input_buffer)? {
+            output.push((lexed_char, position, start_idx..input_buffer.current_idx));
             continue;
         }
 
         if let Some(lexed_left_bracket) = lex_left_bracket(&mut input_buffer) {
-            output.push(lexed_left_bracket);
+            output.push((
+                lexed_left_bracket,
+                position,
+                start_idx..input_buffer.current_idx,
+            ));
             continue;
         }
 
         if let Some(lexed_right_bracket) = lex_right_bracket(&mut input_buffer) {
-            output.push(lexed_right_bracket);
+            output.push((
+                lexed_right_bracket,
+                position,
+                start_idx..input_buffer.current_idx,
+            ));
             continue;
         }
 
@@ -96,18 +249,38 @@ pub fn lex_input(input: &str) -> Result<Vec<LexToken>, &'static str> {
             continue;
         }
 
+        if lex_fold_case_directive(&mut input_buffer) {
+            continue;
+        }
+
         if let Some(lexed_symbol) = lex_symbol(&mut input_buffer) {
-            output.push(lexed_symbol);
+            output.push((lexed_symbol, position, start_idx..input_buffer.current_idx));
             continue;
         }
+
+        return Err("Unexpected character");
     }
 
     Ok(output)
 }
 
-fn lex_string(input: &mut InputBuffer) -> Option<LexToken> {
+fn without_positions(input: Vec<(LexToken, Position)>) -> Vec<LexToken> {
+    input.into_iter().map(|(token, _)| token).collect()
+}
+
+fn skip_shebang(input: &mut InputBuffer) {
+    if !input.input.starts_with("#!/") && !input.input.starts_with("#! ") {
+        return;
+    }
+
+    let shebang = input.read_while(|char| *char != '\n');
+
+    input.skip(shebang.len());
+}
+
+fn lex_string(input: &mut InputBuffer) -> Result<Option<LexToken>, &'static str> {
     if !input.next_char_is(|char| char == '"') {
-        return None;
+        return Ok(None);
     }
 
     input.skip(1);
@@ -115,7 +288,10 @@ fn lex_string(input: &mut InputBuffer) -> Option<LexToken> {
     let mut output = String::from("");
     let mut escape_next_char = false;
     loop {
-        let next_char = input.take_next();
+        let next_char = match input.take_next() {
+            Some(next_char) => next_char,
+            None => return Err(MISSING_CLOSING_QUOTE),
+        };
 
         if next_char == '\"' && !escape_next_char {
             break;
@@ -131,7 +307,39 @@ fn lex_string(input: &mut InputBuffer) -> Option<LexToken> {
         output.push(next_char);
     }
 
-    Some(LexToken::String(output))
+    Ok(Some(LexToken::String(output)))
+}
+
+fn lex_pipe_symbol(input: &mut InputBuffer) -> Result<Option<LexToken>, &'static str> {
+    if !input.next_char_is(|char| char == '|') {
+        return Ok(None);
+    }
+
+    input.skip(1);
+
+    let mut output = String::from("");
+    let mut escape_next_char = false;
+    loop {
+        let next_char = match input.take_next() {
+            Some(next_char) => next_char,
+            None => return Err(MISSING_CLOSING_PIPE),
+        };
+
+        if next_char == '|' && !escape_next_char {
+            break;
+        }
+
+        if next_char == '\\' && !escape_next_char {
+            escape_next_char = true;
+            continue;
+        }
+
+        escape_next_char = false;
+
+        output.push(next_char);
+    }
+
+    Ok(Some(LexToken::Symbol(output)))
 }
 
 fn lex_left_bracket(input: &mut InputBuffer) -> Option<LexToken> {
@@ -154,9 +362,104 @@ fn lex_right_bracket(input: &mut InputBuffer) -> Option<LexToken> {
     Some(LexToken::RightBracket)
 }
 
+fn lex_quote(input: &mut InputBuffer) -> Option<LexToken> {
+    if !input.next_char_is(|char| char == '\'') {
+        return None;
+    }
+
+    input.skip(1);
+
+    Some(LexToken::Quote)
+}
+
+fn lex_vector_start(input: &mut InputBuffer) -> Option<LexToken> {
+    if !input.next_chars_are("#(") {
+        return None;
+    }
+
+    input.skip(2);
+
+    Some(LexToken::VectorStart)
+}
+
+fn lex_bytevector_start(input: &mut InputBuffer) -> Option<LexToken> {
+    if !input.next_chars_are("#u8(") {
+        return None;
+    }
+
+    input.skip(4);
+
+    Some(LexToken::BytevectorStart)
+}
+
+fn lex_datum_comment(input: &mut InputBuffer) -> Option<LexToken> {
+    if !input.next_chars_are("#;") {
+        return None;
+    }
+
+    input.skip(2);
+
+    Some(LexToken::DatumComment)
+}
+
+fn lex_char(input: &mut InputBuffer) -> Result<Option<LexToken>, &'static str> {
+    if !input.next_chars_are("#\\") {
+        return Ok(None);
+    }
+
+    input.skip(2);
+
+    let first_char = match input.take_next() {
+        Some(first_char) => first_char,
+        None => return Err("Missing character after #\\"),
+    };
+    let rest = input.take_while(|char| !char.is_whitespace() && *char != '(' && *char != ')');
+
+    if rest.is_empty() {
+        return Ok(Some(LexToken::Char(first_char)));
+    }
+
+    let mut name = format!("{}{}", first_char, rest);
+
+    if input.fold_case {
+        name = name.to_lowercase();
+    }
+
+    let named_char = match name.as_str() {
+        "alarm" => Some('\u{7}'),
+        "backspace" => Some('\u{8}'),
+        "delete" => Some('\u{7f}'),
+        "escape" => Some('\u{1b}'),
+        "newline" => Some('\n'),
+        "null" => Some('\0'),
+        "return" => Some('\r'),
+        "space" => Some(' '),
+        "tab" => Some('\t'),
+        _ if name.starts_with('x') => u32::from_str_radix(&name[1..], 16)
+            .ok()
+            .and_then(std::char::from_u32),
+        _ => None,
+    };
+
+    match named_char {
+        Some(char) => Ok(Some(LexToken::Char(char))),
+        None => Err("Unknown character name"),
+    }
+}
+
+fn lex_dot(input: &mut InputBuffer) -> Option<LexToken> {
+    if input.read_while(|char| !char.is_whitespace() && *char != '(' && *char != ')') != "." {
+        return None;
+    }
+
+    input.skip(1);
+
+    Some(LexToken::Dot)
+}
+
 fn lex_whitespace(input: &mut InputBuffer) -> bool {
     if input.next_char_is(|char| char.is_whitespace()) {
-        input.skip(1);
+        input.take_next();
         return true;
     }
 
@@ -173,17 +476,46 @@ fn lex_number(input: &mut InputBuffer) -> Option<LexToken> {
 
     match num_as_string.parse::<f64>() {
         Ok(num) => {
-            input.skip(num_as_string.chars().count());
+            input.skip(num_as_string.len());
             Some(LexToken::Num(num))
         }
         Err(_) => None,
     }
 }
 
+fn lex_fold_case_directive(input: &mut InputBuffer) -> bool {
+    let directive = input.read_while(|char| !char.is_whitespace() && *char != '(' && *char != ')');
+
+    let fold_case = match directive {
+        "#!fold-case" => true,
+        "#!no-fold-case" => false,
+        _ => return false,
+    };
+
+    input.fold_case = fold_case;
+    input.skip(directive.len());
+
+    true
+}
+
 fn lex_symbol(input: &mut InputBuffer) -> Option<LexToken> {
-    let output = input.take_while(|char| !char.is_whitespace() && *char != '(' && *char != ')');
+    let mut output = input
+        .take_while(|char| !char.is_whitespace() && *char != '(' && *char != ')')
+        .to_string();
 
-    Some(LexToken::Symbol(output))
+    if output.is_empty() {
+        return None;
+    }
+
+    if input.fold_case {
+        output = output.to_lowercase();
+    }
+
+    match output.as_str() {
+        "#t" | "#true" => Some(LexToken::Bool(true)),
+        "#f" | "#false" => Some(LexToken::Bool(false)),
+        _ => Some(LexToken::Symbol(output)),
+    }
 }
 
 #[cfg(test)]
@@ -236,6 +568,9 @@ mod tests {
         for (input, expect) in tests {
             compare(input, vec![expect]);
         }
+
+        assert_eq!(lex_input(r#""scheme"#), Err(MISSING_CLOSING_QUOTE));
+        assert_eq!(lex_input(r#""scheme\""#), Err(MISSING_CLOSING_QUOTE));
     }
 
     #[test]
@@ -266,6 +601,21 @@ mod tests {
         compare(input, expected_output);
     }
 
+    #[test]
+    fn lex_unicode_whitespace() {
+        let input = "a\u{a0}b\u{2028}(\u{3000}c)";
+
+        let expected_output = vec![
+            LexToken::Symbol("a".to_string()),
+            LexToken::Symbol("b".to_string()),
+            LexToken::LeftBracket,
+            LexToken::Symbol("c".to_string()),
+            LexToken::RightBracket,
+        ];
+
+        compare(input, expected_output);
+    }
+
     #[test]
     fn lex_number() {
         let tests = vec![
@@ -303,6 +653,8 @@ mod tests {
             ("-", LexToken::Symbol("-".to_string())),
             ("e", LexToken::Symbol("e".to_string())),
             ("#symbol", LexToken::Symbol("#symbol".to_string())),
+            ("#t", LexToken::Bool(true)),
+            ("#false", LexToken::Bool(false)),
         ];
 
         for (input, expect) in tests {
@@ -310,6 +662,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lex_pipe_symbol() {
+        let tests = vec![
+            (
+                "|little schemer|",
+                LexToken::Symbol("little schemer".to_string()),
+            ),
+            ("|(|", LexToken::Symbol("(".to_string())),
+            ("||", LexToken::Symbol("".to_string())),
+            (r#"|a\|b|"#, LexToken::Symbol("a|b".to_string())),
+            (r#"|a\\b|"#, LexToken::Symbol("a\\b".to_string())),
+            ("|123|", LexToken::Symbol("123".to_string())),
+        ];
+
+        for (input, expect) in tests {
+            compare(input, vec![expect]);
+        }
+
+        assert_eq!(
+            lex_input_folding_case("|Foo|").unwrap(),
+            vec![LexToken::Symbol("Foo".to_string())]
+        );
+        assert_eq!(lex_input("|foo"), Err("Missing closing | in symbol"));
+    }
+
     #[test]
     fn lex_list_of_symbols() {
         let input = "(somefunc #some_symbol +)";
@@ -325,6 +702,203 @@ mod tests {
         compare(input, expected_output);
     }
 
+    #[test]
+    fn lex_dotted_list() {
+        let input = "(a .b . .5)";
+
+        let expected_output = vec![
+            LexToken::LeftBracket,
+            LexToken::Symbol("a".to_string()),
+            LexToken::Symbol(".b".to_string()),
+            LexToken::Dot,
+            LexToken::Num(0.5),
+            LexToken::RightBracket,
+        ];
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn lex_shebang() {
+        let tests = vec![
+            (
+                "#!/usr/bin/env littleschemer\n(display 1)",
+                vec![
+                    LexToken::LeftBracket,
+                    LexToken::Symbol("display".to_string()),
+                    LexToken::Num(1.0),
+                    LexToken::RightBracket,
+                ],
+            ),
+            ("#! /usr/bin/littleschemer", vec![]),
+            (
+                "a\n#!/usr/bin/env littleschemer",
+                vec![
+                    LexToken::Symbol("a".to_string()),
+                    LexToken::Symbol("#!/usr/bin/env".to_string()),
+                    LexToken::Symbol("littleschemer".to_string()),
+                ],
+            ),
+        ];
+
+        for (input, expect) in tests {
+            compare(input, expect);
+        }
+    }
+
+    #[test]
+    fn lex_quoted_vector() {
+        let input = "'#(1 #\\a #t)";
+
+        let expected_output = vec![
+            LexToken::Quote,
+            LexToken::VectorStart,
+            LexToken::Num(1.0),
+            LexToken::Char('a'),
+            LexToken::Bool(true),
+            LexToken::RightBracket,
+        ];
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn lex_bytevector() {
+        let input = "#u8(0 255)";
+
+        let expected_output = vec![
+            LexToken::BytevectorStart,
+            LexToken::Num(0.0),
+            LexToken::Num(255.0),
+            LexToken::RightBracket,
+        ];
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn lex_datum_comment() {
+        let input = "(a #;b #; (c))";
+
+        let expected_output = vec![
+            LexToken::LeftBracket,
+            LexToken::Symbol("a".to_string()),
+            LexToken::DatumComment,
+            LexToken::Symbol("b".to_string()),
+            LexToken::DatumComment,
+            LexToken::LeftBracket,
+            LexToken::Symbol("c".to_string()),
+            LexToken::RightBracket,
+            LexToken::RightBracket,
+        ];
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn lex_char() {
+        let tests = vec![
+            ("#\\a", LexToken::Char('a')),
+            ("#\\(", LexToken::Char('(')),
+            ("#\\)", LexToken::Char(')')),
+            ("#\\ ", LexToken::Char(' ')),
+            ("#\\space", LexToken::Char(' ')),
+            ("#\\newline", LexToken::Char('\n')),
+            ("#\\x", LexToken::Char('x')),
+            ("#\\x41", LexToken::Char('A')),
+            ("#\\λ", LexToken::Char('λ')),
+        ];
+
+        for (input, expect) in tests {
+            compare(input, vec![expect]);
+        }
+
+        assert_eq!(lex_input("#\\nonsense"), Err("Unknown character name"));
+        assert_eq!(lex_input("#\\"), Err("Missing character after #\\"));
+    }
+
+    #[test]
+    fn lex_fold_case() {
+        let input = r#"(Foo #!fold-case Foo #\SPACE #\X41 "Bar" #!no-fold-case Foo)"#;
+
+        let expected_output = vec![
+            LexToken::LeftBracket,
+            LexToken::Symbol("Foo".to_string()),
+            LexToken::Symbol("foo".to_string()),
+            LexToken::Char(' '),
+            LexToken::Char('A'),
+            LexToken::String("Bar".to_string()),
+            LexToken::Symbol("Foo".to_string()),
+            LexToken::RightBracket,
+        ];
+
+        compare(input, expected_output);
+
+        let expected_output = vec![
+            LexToken::LeftBracket,
+            LexToken::Symbol("foo".to_string()),
+            LexToken::Symbol("Foo".to_string()),
+            LexToken::RightBracket,
+        ];
+
+        assert_eq!(
+            lex_input_folding_case("(Foo #!no-fold-case Foo)").unwrap(),
+            expected_output
+        );
+
+        assert_eq!(lex_input(r#"#\SPACE"#), Err("Unknown character name"));
+    }
+
+    #[test]
+    fn lex_positions() {
+        let input = "(a\n  \"b\nc\" d)\n'e";
+
+        let expected_output = vec![
+            (LexToken::LeftBracket, Position { line: 1, column: 1 }),
+            (
+                LexToken::Symbol("a".to_string()),
+                Position { line: 1, column: 2 },
+            ),
+            (
+                LexToken::String("b\nc".to_string()),
+                Position { line: 2, column: 3 },
+            ),
+            (
+                LexToken::Symbol("d".to_string()),
+                Position { line: 3, column: 4 },
+            ),
+            (LexToken::RightBracket, Position { line: 3, column: 5 }),
+            (LexToken::Quote, Position { line: 4, column: 1 }),
+            (
+                LexToken::Symbol("e".to_string()),
+                Position { line: 4, column: 2 },
+            ),
+        ];
+
+        assert_eq!(
+            lex_input_with_positions(input, false).unwrap(),
+            expected_output
+        );
+    }
+
+    #[test]
+    fn lex_ranges() {
+        let input = "#!/bin/ls\n(a \"λ\" #\\space)";
+
+        let expected_output = vec![
+            (LexToken::LeftBracket, 10..11),
+            (LexToken::Symbol("a".to_string()), 11..12),
+            (LexToken::String("λ".to_string()), 13..17),
+            (LexToken::Char(' '), 18..25),
+            (LexToken::RightBracket, 25..26),
+        ];
+
+        assert_eq!(
+            lex_input_with_ranges(input, false).unwrap(),
+            expected_output
+        );
+    }
+
     #[test]
     fn lex_fizzbuzz() {
         let input = r#"
@@ -429,7 +1003,7 @@ mod tests {
             LexToken::String("buzz".to_string()),
             LexToken::RightBracket,
             LexToken::LeftBracket,
-            LexToken::Symbol("#t".to_string()),
+            LexToken::Bool(true),
             LexToken::LeftBracket,
             LexToken::Symbol("number->string".to_string()),
             LexToken::Symbol("num".to_string()),
@@ -485,6 +1059,47 @@ mod tests {
         compare(input, expected_output);
     }
 
+    #[test]
+    fn lex_arbitrary_input_without_panicking() {
+        // Characters that start or end tokens are far more likely to find
+        // edge cases than uniformly random ones, so most inputs draw from
+        // them. Multi-byte characters, including whitespace, catch offsets
+        // that stop part way through a character.
+        let interesting_chars =
+            "()#\\|\";'.-e0123456789u8tf! \n\tabcxλé\u{301}😀\u{a0}\u{2028}\u{3000}"
+                .chars()
+                .collect::<Vec<char>>();
+        let mut seed: u64 = 0x5eed_1e55_c0de_cafe;
+
+        let mut next_random = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+
+        for _ in 0..5000 {
+            let length = (next_random() % 32) as usize;
+            let use_any_char = next_random() % 4 == 0;
+
+            let input = (0..length)
+                .map(|_| {
+                    let random = next_random();
+
+                    if use_any_char {
+                        std::char::from_u32((random % 0x11_0000) as u32).unwrap_or('\u{fffd}')
+                    } else {
+                        interesting_chars[(random as usize) % interesting_chars.len()]
+                    }
+                })
+                .collect::<String>();
+
+            let result = std::panic::catch_unwind(|| lex_input(&input));
+
+            assert!(result.is_ok(), "lexer panicked on {:?}", input);
+        }
+    }
+
     fn compare(input: &str, expected_output: Vec<LexToken>) {
         let actual_output = lex_input(input).unwrap();
 
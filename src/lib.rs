@@ -0,0 +1,4 @@
+pub mod highlight;
+pub mod lexer;
+pub mod parser;
+pub mod printer;
@@ -1,27 +1,321 @@
-use std::io::{self, Write};
+use little_schemer::highlight::{self, Format};
+use little_schemer::{lexer, parser, printer};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Editor, Helper};
+use std::borrow::Cow;
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::Path;
+use std::process;
+use std::thread;
 
-mod lexer;
-mod parser;
+mod history;
+
+const USAGE: &str = "Usage: little-schemer [command] [options]
+
+Commands:
+    repl                 Start the interactive REPL (the default)
+    run <file>           Read a file, printing each form
+    eval <expr>          Read an expression, printing each form
+    highlight <file>     Print a file with syntax highlighting
+
+Options:
+    --fold-case          Fold symbols and character names to lower case
+    --no-history         Don't load or save REPL history
+    --format=ansi|html   Output format for highlight (default: ansi)
+    --help               Print this message";
+
+struct Options {
+    fold_case: bool,
+    keep_history: bool,
+    format: Format,
+}
 
 fn main() {
-    println!("Little Scheme In Rust");
+    let args = env::args().skip(1).collect::<Vec<String>>();
 
-    loop {
-        let input = get_input();
+    let (flags, operands): (Vec<&str>, Vec<&str>) = args
+        .iter()
+        .map(String::as_str)
+        .partition(|arg| arg.starts_with("--"));
 
-        println!("{:?}", lexer::lex_input(&input));
+    let mut options = Options {
+        fold_case: false,
+        keep_history: true,
+        format: Format::Ansi,
+    };
+
+    for flag in flags {
+        match flag {
+            "--fold-case" => options.fold_case = true,
+            "--no-history" => options.keep_history = false,
+            "--format=ansi" => options.format = Format::Ansi,
+            "--format=html" => options.format = Format::Html,
+            "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            _ => usage_error(&format!("Unknown option: {}", flag)),
+        }
+    }
+
+    match operands.as_slice() {
+        [] | ["repl"] => run_repl(&options),
+        ["run", path] => run_file(path, &options),
+        ["eval", expr] => eval_expr(expr, &options),
+        ["highlight", path] => highlight_file(path, &options),
+        [command @ "compile", ..] | [command @ "fmt", ..] | [command @ "test", ..] => {
+            eprintln!("The {} command isn't implemented yet", command);
+            process::exit(1);
+        }
+        // Running a file without a command predates the subcommands.
+        [path] => run_file(path, &options),
+        _ => usage_error("Unexpected arguments"),
     }
 }
 
-fn get_input() -> String {
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    eprintln!();
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn run_repl(options: &Options) {
+    println!("Little Scheme In Rust");
+
+    let mut editor = Editor::new().unwrap_or_else(|err| {
+        eprintln!("Could not start line editor: {}", err);
+        process::exit(1);
+    });
+
+    editor.set_helper(Some(SchemeHelper {
+        fold_case: options.fold_case,
+    }));
+
+    let history_path = history::history_path().filter(|_| options.keep_history);
+
+    if let Some(path) = &history_path {
+        // A missing history file just means this is the first session.
+        let _ = editor.load_history(path);
+    }
+
     let mut input = String::new();
 
-    print!("user> ");
-    let _ = io::stdout().flush();
+    while let Some(line) = get_input(
+        &mut editor,
+        if input.is_empty() { "user> " } else { "...   " },
+    ) {
+        let line = match line {
+            Input::Line(line) => line,
+            // Ctrl-C abandons the entry being typed, not the session.
+            Input::Interrupted => {
+                input.clear();
+                continue;
+            }
+        };
+
+        if input.is_empty() && line.trim().is_empty() {
+            continue;
+        }
+
+        if !input.is_empty() {
+            input.push('\n');
+        }
+
+        input.push_str(&line);
 
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Could not read line from STDIN");
+        match catch_panic(|| run_input(&input, options.fold_case)) {
+            Ok(Ok(None)) => continue,
+            Ok(Ok(Some(outputs))) => {
+                for output in outputs {
+                    println!("{}", output);
+                }
+            }
+            Ok(Err(err)) => println!("Error: {}", err),
+            Err(panic) => println!("Internal error: {}", panic_message(&panic)),
+        }
 
-    input.trim().to_string()
+        let _ = editor.add_history_entry(input.as_str());
+
+        input.clear();
+    }
+
+    println!();
+
+    // Input can end part way through an expression, e.g. when it is piped
+    // in, which would otherwise be dropped without a word.
+    if !input.is_empty() {
+        println!("Error: {}", parser::UNEXPECTED_END_OF_INPUT);
+
+        let _ = editor.add_history_entry(input.as_str());
+    }
+
+    if let Some(path) = &history_path {
+        save_history(&mut editor, path);
+    }
+
+    println!("Goodbye");
+}
+
+/// Prints a whole file with its tokens annotated for the chosen format.
+fn highlight_file(path: &str, options: &Options) {
+    let input = read_file(path);
+
+    print!(
+        "{}",
+        highlight::highlight(&input, options.fold_case, options.format)
+    );
+}
+
+/// Parses a whole file, printing each form.
+fn run_file(path: &str, options: &Options) {
+    for expr in read_program(path, &read_file(path), options) {
+        println!("{}", printer::print_expr(&parser::desugar(expr)));
+    }
+}
+
+fn eval_expr(input: &str, options: &Options) {
+    for expr in read_program("<eval>", input, options) {
+        println!("{}", printer::print_expr(&parser::desugar(expr)));
+    }
+}
+
+fn read_file(path: &str) -> String {
+    fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Could not read {}: {}", path, err);
+        process::exit(1);
+    })
+}
+
+/// Parses every form in the input or, if there are syntax errors, prints
+/// every error found before exiting with a failure status.
+fn read_program(name: &str, input: &str, options: &Options) -> Vec<parser::Expr> {
+    let tokens = lexer::lex_input_with_positions(input, options.fold_case).unwrap_or_else(|err| {
+        eprintln!("{}: {}", name, err);
+        process::exit(1);
+    });
+
+    parser::parse_program(tokens).unwrap_or_else(|errors| {
+        for error in errors {
+            eprintln!(
+                "{}:{}:{}: {}",
+                name, error.position.line, error.position.column, error.message
+            );
+        }
+        process::exit(1);
+    })
+}
+
+/// Returns the output for each form in the input, or `None` if the last
+/// form is incomplete and more lines are needed.
+fn run_input(input: &str, fold_case: bool) -> Result<Option<Vec<String>>, &'static str> {
+    let tokens = match lexer::lex_input_with_positions(input, fold_case) {
+        Ok(tokens) => tokens,
+        Err(lexer::MISSING_CLOSING_QUOTE) | Err(lexer::MISSING_CLOSING_PIPE) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+
+    match parser::try_parse_program(tokens)? {
+        parser::ParseOutcome::Complete(exprs) => Ok(Some(
+            exprs
+                .into_iter()
+                .map(|expr| printer::print_expr(&parser::desugar(expr)))
+                .collect(),
+        )),
+        parser::ParseOutcome::Incomplete => Ok(None),
+    }
+}
+
+/// Runs `f`, catching any panic so the caller can report it. The panic
+/// hook is silenced meanwhile, since its message and backtrace hint would
+/// only repeat that report, and restored afterwards so panics elsewhere
+/// are still printed.
+fn catch_panic<T>(f: impl FnOnce() -> T + panic::UnwindSafe) -> thread::Result<T> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+
+    let result = panic::catch_unwind(f);
+
+    panic::set_hook(hook);
+
+    result
+}
+
+fn panic_message(panic: &Box<dyn std::any::Any + Send>) -> &str {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        return message;
+    }
+
+    if let Some(message) = panic.downcast_ref::<String>() {
+        return message;
+    }
+
+    "unknown panic"
+}
+
+enum Input {
+    Line(String),
+    /// The user pressed Ctrl-C at the prompt.
+    Interrupted,
+}
+
+/// Returns `None` once STDIN reaches end of file, e.g. after Ctrl-D.
+fn get_input(editor: &mut SchemeEditor, prompt: &str) -> Option<Input> {
+    match editor.readline(prompt) {
+        Ok(line) => Some(Input::Line(line)),
+        Err(ReadlineError::Interrupted) => Some(Input::Interrupted),
+        Err(ReadlineError::Eof) => None,
+        Err(err) => {
+            eprintln!("Could not read line from STDIN: {}", err);
+            None
+        }
+    }
+}
+
+/// Appends this session's entries, so concurrent sessions don't overwrite
+/// each other's history.
+fn save_history(editor: &mut SchemeEditor, path: &Path) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+
+    if let Err(err) = editor.append_history(path) {
+        eprintln!("Could not save history to {}: {}", path.display(), err);
+    }
+}
+
+type SchemeEditor = Editor<SchemeHelper, DefaultHistory>;
+
+/// Colours the line being edited at the prompt.
+struct SchemeHelper {
+    fold_case: bool,
+}
+
+impl Helper for SchemeHelper {}
+
+impl Completer for SchemeHelper {
+    type Candidate = String;
+}
+
+impl Hinter for SchemeHelper {
+    type Hint = String;
+}
+
+impl Validator for SchemeHelper {}
+
+impl Highlighter for SchemeHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        Cow::Owned(highlight::highlight(line, self.fold_case, Format::Ansi))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _forced: bool) -> bool {
+        true
+    }
 }
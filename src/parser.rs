@@ -1,15 +1,430 @@
-use crate::lexer::LexToken;
+use crate::lexer::{LexToken, Position};
+use std::iter::Peekable;
+use std::vec::IntoIter;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expr {
     Num(f64),
+    Bool(bool),
+    Char(char),
     Symbol(String),
     String(String),
     List(Vec<Expr>),
+    Vector(Vec<Expr>),
+    Bytevector(Vec<u8>),
+    DottedList(Vec<Expr>, Box<Expr>),
+}
+
+/// The result of parsing input that may not have been fully entered yet.
+#[derive(Debug, PartialEq)]
+pub enum ParseOutcome<T> {
+    Complete(T),
+    /// The tokens ran out before the expression was finished, e.g. a
+    /// missing closing bracket, so more input could still complete it.
+    Incomplete,
+}
+
+/// A syntax error in a program, located at the start of the top-level
+/// form it was found in.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub position: Position,
+    pub message: &'static str,
+}
+
+const MISSING_CLOSING_BRACKET: &str = "Missing closing bracket";
+pub const UNEXPECTED_END_OF_INPUT: &str = "Unexpected end of input";
+
+struct TokenBuffer {
+    tokens: Peekable<IntoIter<LexToken>>,
+}
+
+impl TokenBuffer {
+    fn from_tokens(tokens: Vec<LexToken>) -> TokenBuffer {
+        TokenBuffer {
+            tokens: tokens.into_iter().peekable(),
+        }
+    }
+
+    fn has_tokens_remaining(&mut self) -> bool {
+        self.tokens.peek().is_some()
+    }
+
+    fn peek(&mut self) -> Option<&LexToken> {
+        self.tokens.peek()
+    }
+
+    fn take_next(&mut self) -> Option<LexToken> {
+        self.tokens.next()
+    }
 }
 
 pub fn parse_tokens(input: Vec<LexToken>) -> Result<Expr, &'static str> {
-    Ok(Expr::Symbol("little-schemer".to_string()))
+    match parse_form(input)? {
+        Some(output) => Ok(output),
+        None => Err(UNEXPECTED_END_OF_INPUT),
+    }
+}
+
+/// Parses every top-level form in a program. A syntax error in one form
+/// doesn't stop the rest from being parsed, so all of them are reported
+/// in one go.
+pub fn parse_program(input: Vec<(LexToken, Position)>) -> Result<Vec<Expr>, Vec<ParseError>> {
+    let mut output = Vec::new();
+    let mut errors = Vec::new();
+
+    for form in split_top_level_forms(input) {
+        // A form left open would only be reported as missing a closing
+        // bracket. An opening bracket in the first column most likely
+        // starts a new form, so splitting there limits the damage to the
+        // form that is actually missing one.
+        let forms = if has_unclosed_brackets(&form) {
+            split_at_first_column_brackets(form)
+                .into_iter()
+                .flat_map(split_top_level_forms)
+                .collect()
+        } else {
+            vec![form]
+        };
+
+        for form in forms {
+            match parse_located_form(form) {
+                Ok(expr) => output.extend(expr),
+                Err(error) => errors.push(error),
+            }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    Ok(output)
+}
+
+/// Parses a single datum, returning `None` if the input only holds datum
+/// comments.
+fn parse_form(input: Vec<LexToken>) -> Result<Option<Expr>, &'static str> {
+    let mut token_buffer = TokenBuffer::from_tokens(input);
+
+    skip_datum_comments(&mut token_buffer)?;
+
+    if !token_buffer.has_tokens_remaining() {
+        return Ok(None);
+    }
+
+    let output = parse_expr(&mut token_buffer)?;
+
+    skip_datum_comments(&mut token_buffer)?;
+
+    if token_buffer.has_tokens_remaining() {
+        return Err("Unexpected input after expression");
+    }
+
+    Ok(Some(output))
+}
+
+/// Like `parse_form`, but locates any error at the start of the form.
+fn parse_located_form(form: Vec<(LexToken, Position)>) -> Result<Option<Expr>, ParseError> {
+    let position = form[0].1;
+    let tokens = form.into_iter().map(|(token, _)| token).collect();
+
+    parse_form(tokens).map_err(|message| ParseError { position, message })
+}
+
+/// Groups tokens into top-level forms by bracket depth, ready to be parsed
+/// independently.
+fn split_top_level_forms(input: Vec<(LexToken, Position)>) -> Vec<Vec<(LexToken, Position)>> {
+    let mut output: Vec<Vec<(LexToken, Position)>> = Vec::new();
+    let mut depth = 0;
+    let mut datums_needed = 0;
+
+    for (token, position) in input {
+        if datums_needed == 0 {
+            output.push(Vec::new());
+            depth = 0;
+            datums_needed = 1;
+        }
+
+        // A stray closing bracket at depth 0 counts as a datum, leaving it
+        // in a form of its own for the parser to reject. A datum comment
+        // inside a list is the list's business, not the top level's.
+        match token {
+            LexToken::DatumComment if depth == 0 => datums_needed += 1,
+            LexToken::RightBracket if depth > 0 => depth -= 1,
+            _ if is_opening_bracket(&token) => depth += 1,
+            _ => {}
+        }
+
+        if depth == 0 && !matches!(token, LexToken::DatumComment | LexToken::Quote) {
+            datums_needed -= 1;
+        }
+
+        output
+            .last_mut()
+            .expect("A form is always started before the first token")
+            .push((token, position));
+    }
+
+    output
+}
+
+/// Splits a form before every opening bracket in the first column.
+fn split_at_first_column_brackets(
+    input: Vec<(LexToken, Position)>,
+) -> Vec<Vec<(LexToken, Position)>> {
+    let mut output: Vec<Vec<(LexToken, Position)>> = Vec::new();
+
+    for (token, position) in input {
+        if output.is_empty() || (position.column == 1 && is_opening_bracket(&token)) {
+            output.push(Vec::new());
+        }
+
+        output
+            .last_mut()
+            .expect("A piece is always started before the first token")
+            .push((token, position));
+    }
+
+    output
+}
+
+fn has_unclosed_brackets(form: &[(LexToken, Position)]) -> bool {
+    let opening = form
+        .iter()
+        .filter(|(token, _)| is_opening_bracket(token))
+        .count();
+    let closing = form
+        .iter()
+        .filter(|(token, _)| *token == LexToken::RightBracket)
+        .count();
+
+    opening > closing
+}
+
+fn is_opening_bracket(token: &LexToken) -> bool {
+    matches!(
+        token,
+        LexToken::LeftBracket | LexToken::VectorStart | LexToken::BytevectorStart
+    )
+}
+
+/// Like `parse_tokens`, but distinguishes input that simply stops early
+/// from input that can never parse, so callers such as the REPL can
+/// decide whether to keep reading.
+pub fn try_parse_tokens(input: Vec<LexToken>) -> Result<ParseOutcome<Expr>, &'static str> {
+    match parse_tokens(input) {
+        Ok(expr) => Ok(ParseOutcome::Complete(expr)),
+        Err(MISSING_CLOSING_BRACKET) | Err(UNEXPECTED_END_OF_INPUT) => Ok(ParseOutcome::Incomplete),
+        Err(err) => Err(err),
+    }
+}
+
+/// Like `try_parse_tokens`, but for input holding any number of top-level
+/// forms, such as an entry at the REPL. Only an unfinished last form makes
+/// the input incomplete; an error in any form is returned as it is.
+pub fn try_parse_program(
+    input: Vec<(LexToken, Position)>,
+) -> Result<ParseOutcome<Vec<Expr>>, &'static str> {
+    let forms = split_top_level_forms(input);
+    let last_idx = forms.len().saturating_sub(1);
+    let mut output = Vec::new();
+
+    for (idx, form) in forms.into_iter().enumerate() {
+        let tokens = form.into_iter().map(|(token, _)| token).collect();
+
+        match parse_form(tokens) {
+            Ok(expr) => output.extend(expr),
+            Err(MISSING_CLOSING_BRACKET) | Err(UNEXPECTED_END_OF_INPUT) if idx == last_idx => {
+                return Ok(ParseOutcome::Incomplete)
+            }
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(ParseOutcome::Complete(output))
+}
+
+fn parse_expr(input: &mut TokenBuffer) -> Result<Expr, &'static str> {
+    skip_datum_comments(input)?;
+
+    match input.take_next() {
+        Some(LexToken::Num(num)) => Ok(Expr::Num(num)),
+        Some(LexToken::Bool(bool)) => Ok(Expr::Bool(bool)),
+        Some(LexToken::Symbol(symbol)) => Ok(Expr::Symbol(symbol)),
+        Some(LexToken::String(string)) => Ok(Expr::String(string)),
+        Some(LexToken::Char(char)) => Ok(Expr::Char(char)),
+        Some(LexToken::Quote) => parse_quote(input),
+        Some(LexToken::LeftBracket) => parse_list(input),
+        Some(LexToken::VectorStart) => parse_vector(input),
+        Some(LexToken::BytevectorStart) => parse_bytevector(input),
+        Some(LexToken::RightBracket) => Err("Unexpected closing bracket"),
+        Some(LexToken::Dot) => Err("Unexpected dot outside of list"),
+        Some(LexToken::DatumComment) => unreachable!("Datum comments are skipped above"),
+        None => Err(UNEXPECTED_END_OF_INPUT),
+    }
+}
+
+/// Discards each `#;` along with the datum that follows it.
+fn skip_datum_comments(input: &mut TokenBuffer) -> Result<(), &'static str> {
+    while let Some(LexToken::DatumComment) = input.peek() {
+        input.take_next();
+
+        if let Some(LexToken::RightBracket) = input.peek() {
+            return Err("Datum comment must be followed by a datum");
+        }
+
+        parse_expr(input)?;
+    }
+
+    Ok(())
+}
+
+fn parse_quote(input: &mut TokenBuffer) -> Result<Expr, &'static str> {
+    let quoted = parse_expr(input)?;
+
+    Ok(Expr::List(vec![Expr::Symbol("quote".to_string()), quoted]))
+}
+
+fn parse_vector(input: &mut TokenBuffer) -> Result<Expr, &'static str> {
+    let mut items = Vec::new();
+
+    loop {
+        skip_datum_comments(input)?;
+
+        match input.peek() {
+            Some(LexToken::RightBracket) => {
+                input.take_next();
+                return Ok(Expr::Vector(items));
+            }
+            Some(LexToken::Dot) => return Err("Unexpected dot in vector"),
+            Some(_) => items.push(parse_expr(input)?),
+            None => return Err(MISSING_CLOSING_BRACKET),
+        }
+    }
+}
+
+fn parse_bytevector(input: &mut TokenBuffer) -> Result<Expr, &'static str> {
+    let mut bytes = Vec::new();
+
+    loop {
+        skip_datum_comments(input)?;
+
+        match input.take_next() {
+            Some(LexToken::RightBracket) => return Ok(Expr::Bytevector(bytes)),
+            Some(LexToken::Num(num)) if num.fract() == 0.0 && (0.0..=255.0).contains(&num) => {
+                bytes.push(num as u8)
+            }
+            Some(_) => return Err("Bytevector items must be integers from 0 to 255"),
+            None => return Err(MISSING_CLOSING_BRACKET),
+        }
+    }
+}
+
+fn parse_list(input: &mut TokenBuffer) -> Result<Expr, &'static str> {
+    let mut items = Vec::new();
+
+    loop {
+        skip_datum_comments(input)?;
+
+        match input.peek() {
+            Some(LexToken::RightBracket) => {
+                input.take_next();
+                return Ok(Expr::List(items));
+            }
+            Some(LexToken::Dot) => {
+                input.take_next();
+                return parse_dotted_tail(input, items);
+            }
+            Some(_) => items.push(parse_expr(input)?),
+            None => return Err(MISSING_CLOSING_BRACKET),
+        }
+    }
+}
+
+fn parse_dotted_tail(input: &mut TokenBuffer, items: Vec<Expr>) -> Result<Expr, &'static str> {
+    if items.is_empty() {
+        return Err("Dotted list must have at least one item before the dot");
+    }
+
+    skip_datum_comments(input)?;
+
+    if let Some(LexToken::RightBracket) = input.peek() {
+        return Err("Dotted list must have an item after the dot");
+    }
+
+    let tail = parse_expr(input)?;
+
+    skip_datum_comments(input)?;
+
+    match input.take_next() {
+        Some(LexToken::RightBracket) => Ok(Expr::DottedList(items, Box::new(tail))),
+        Some(_) => Err("Dotted list must have exactly one item after the dot"),
+        None => Err(MISSING_CLOSING_BRACKET),
+    }
+}
+
+/// Desugars the forms in a parsed expression that are shorthand for
+/// others. Quoted data is left as it was read.
+pub fn desugar(input: Expr) -> Expr {
+    match input {
+        Expr::List(items) if is_quotation(&items) => Expr::List(items),
+        Expr::List(items) => {
+            let items = items.into_iter().map(desugar).collect();
+
+            Expr::List(desugar_curried_define(items))
+        }
+        other => other,
+    }
+}
+
+fn is_quotation(items: &[Expr]) -> bool {
+    matches!(
+        items.first(),
+        Some(Expr::Symbol(name)) if name == "quote" || name == "quasiquote"
+    )
+}
+
+/// Rewrites `(define ((f a) b) body)` into `(define (f a) (lambda (b) body))`,
+/// repeating until the header is a flat `(name args...)` form.
+fn desugar_curried_define(mut items: Vec<Expr>) -> Vec<Expr> {
+    loop {
+        if items.len() < 2 || items[0] != Expr::Symbol("define".to_string()) {
+            return items;
+        }
+
+        let (header, params) = match items.remove(1) {
+            Expr::List(mut header) if is_list(header.first()) => {
+                let inner = header.remove(0);
+                (inner, Expr::List(header))
+            }
+            Expr::DottedList(mut header, tail) if is_list(header.first()) => {
+                let inner = header.remove(0);
+                let params = if header.is_empty() {
+                    *tail
+                } else {
+                    Expr::DottedList(header, tail)
+                };
+                (inner, params)
+            }
+            header => {
+                items.insert(1, header);
+                return items;
+            }
+        };
+
+        let body = items.split_off(1);
+
+        let mut lambda = vec![Expr::Symbol("lambda".to_string()), params];
+        lambda.extend(body);
+
+        items.push(header);
+        items.push(Expr::List(lambda));
+    }
+}
+
+fn is_list(expr: Option<&Expr>) -> bool {
+    matches!(expr, Some(Expr::List(_)) | Some(Expr::DottedList(_, _)))
 }
 
 #[cfg(test)]
@@ -27,4 +442,412 @@ mod tests {
 
         assert_eq!(actual_output, expected_output);
     }
+
+    #[test]
+    fn parse_list() {
+        let input = "(define (add a b) (+ a b))";
+
+        let expected_output = Expr::List(vec![
+            Expr::Symbol("define".to_string()),
+            Expr::List(vec![
+                Expr::Symbol("add".to_string()),
+                Expr::Symbol("a".to_string()),
+                Expr::Symbol("b".to_string()),
+            ]),
+            Expr::List(vec![
+                Expr::Symbol("+".to_string()),
+                Expr::Symbol("a".to_string()),
+                Expr::Symbol("b".to_string()),
+            ]),
+        ]);
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn parse_dotted_lambda_list() {
+        let input = "(lambda (a b . rest) rest)";
+
+        let expected_output = Expr::List(vec![
+            Expr::Symbol("lambda".to_string()),
+            Expr::DottedList(
+                vec![Expr::Symbol("a".to_string()), Expr::Symbol("b".to_string())],
+                Box::new(Expr::Symbol("rest".to_string())),
+            ),
+            Expr::Symbol("rest".to_string()),
+        ]);
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn parse_quoted_data() {
+        let input = "'(a #(1 #\\b (#t)) #false \"c\")";
+
+        let expected_output = Expr::List(vec![
+            Expr::Symbol("quote".to_string()),
+            Expr::List(vec![
+                Expr::Symbol("a".to_string()),
+                Expr::Vector(vec![
+                    Expr::Num(1.0),
+                    Expr::Char('b'),
+                    Expr::List(vec![Expr::Bool(true)]),
+                ]),
+                Expr::Bool(false),
+                Expr::String("c".to_string()),
+            ]),
+        ]);
+
+        compare(input, expected_output);
+    }
+
+    #[test]
+    fn parse_bytevector() {
+        compare("#u8()", Expr::Bytevector(vec![]));
+        compare("#u8(0 10 255)", Expr::Bytevector(vec![0, 10, 255]));
+    }
+
+    #[test]
+    fn parse_datum_comment() {
+        let tests = vec![
+            ("#;a b", "b"),
+            ("a #;b", "a"),
+            ("(a #;b c)", "(a c)"),
+            ("(a #;(b c) d)", "(a d)"),
+            ("(a #; #;b c d)", "(a d)"),
+            ("(a #;b)", "(a)"),
+            ("#(1 #;2 3)", "#(1 3)"),
+            ("#u8(1 #;2 3)", "#u8(1 3)"),
+            ("(a . #;b c #;d)", "(a . c)"),
+            ("'#;a b", "'b"),
+        ];
+
+        for (input, expect) in tests {
+            let expected_output = parse_tokens(lex_input(expect).unwrap()).unwrap();
+
+            compare(input, expected_output);
+        }
+    }
+
+    #[test]
+    fn parse_curried_define() {
+        let tests = vec![
+            (
+                "(define ((adder x) y) (+ x y))",
+                "(define (adder x) (lambda (y) (+ x y)))",
+            ),
+            (
+                "(define (((f a) b) c) a b c)",
+                "(define (f a) (lambda (b) (lambda (c) a b c)))",
+            ),
+            (
+                "(define ((f a) . rest) rest)",
+                "(define (f a) (lambda rest rest))",
+            ),
+            (
+                "(define ((f a) b . rest) rest)",
+                "(define (f a) (lambda (b . rest) rest))",
+            ),
+            ("(define (f x) x)", "(define (f x) x)"),
+            ("(define x 1)", "(define x 1)"),
+        ];
+
+        for (input, expect) in tests {
+            let actual_output = desugar(parse_tokens(lex_input(input).unwrap()).unwrap());
+            let expected_output = parse_tokens(lex_input(expect).unwrap()).unwrap();
+
+            assert_eq!(actual_output, expected_output, "desugaring {:?}", input);
+        }
+    }
+
+    #[test]
+    fn desugar_leaves_data_alone() {
+        let tests = vec![
+            (
+                "(lambda () (define ((f a) b) b))",
+                "(lambda () (define (f a) (lambda (b) b)))",
+            ),
+            ("'(define ((a b) c) d)", "'(define ((a b) c) d)"),
+            (
+                "(quasiquote (define ((a b) c) d))",
+                "(quasiquote (define ((a b) c) d))",
+            ),
+            ("#((define ((a b) c) d))", "#((define ((a b) c) d))"),
+        ];
+
+        for (input, expect) in tests {
+            let actual_output = desugar(parse_tokens(lex_input(input).unwrap()).unwrap());
+            let expected_output = parse_tokens(lex_input(expect).unwrap()).unwrap();
+
+            assert_eq!(actual_output, expected_output, "desugaring {:?}", input);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let tests = vec![
+            ("", "Unexpected end of input"),
+            (")", "Unexpected closing bracket"),
+            ("(a b", "Missing closing bracket"),
+            ("(a) b", "Unexpected input after expression"),
+            (".", "Unexpected dot outside of list"),
+            (
+                "(. a)",
+                "Dotted list must have at least one item before the dot",
+            ),
+            ("(a .)", "Dotted list must have an item after the dot"),
+            (
+                "(a . b c)",
+                "Dotted list must have exactly one item after the dot",
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let actual_output = parse_tokens(lex_input(input).unwrap());
+
+            assert_eq!(actual_output, Err(expect), "parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn parse_quote_and_vector_errors() {
+        let tests = vec![
+            ("'", "Unexpected end of input"),
+            ("#(1 . 2)", "Unexpected dot in vector"),
+            ("#(1 2", "Missing closing bracket"),
+        ];
+
+        for (input, expect) in tests {
+            let actual_output = parse_tokens(lex_input(input).unwrap());
+
+            assert_eq!(actual_output, Err(expect), "parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn parse_bytevector_errors() {
+        let tests = vec![
+            ("#u8(1 2", "Missing closing bracket"),
+            (
+                "#u8(256)",
+                "Bytevector items must be integers from 0 to 255",
+            ),
+            (
+                "#u8(1.5)",
+                "Bytevector items must be integers from 0 to 255",
+            ),
+            ("#u8(a)", "Bytevector items must be integers from 0 to 255"),
+        ];
+
+        for (input, expect) in tests {
+            let actual_output = parse_tokens(lex_input(input).unwrap());
+
+            assert_eq!(actual_output, Err(expect), "parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn parse_datum_comment_errors() {
+        let tests = vec![
+            ("#;", "Unexpected end of input"),
+            ("a #;", "Unexpected end of input"),
+            ("(a #;)", "Datum comment must be followed by a datum"),
+            ("(a #;(b", "Missing closing bracket"),
+        ];
+
+        for (input, expect) in tests {
+            let actual_output = parse_tokens(lex_input(input).unwrap());
+
+            assert_eq!(actual_output, Err(expect), "parsing {:?}", input);
+        }
+    }
+
+    #[test]
+    fn try_parse_incomplete() {
+        let tests = vec![
+            "",
+            "(",
+            "(define (f x)",
+            "(a . ",
+            "(a . b",
+            "'",
+            "#(1",
+            "#u8(1",
+            "(a #;",
+        ];
+
+        for input in tests {
+            let actual_output = try_parse_tokens(lex_input(input).unwrap());
+
+            assert_eq!(
+                actual_output,
+                Ok(ParseOutcome::Incomplete),
+                "parsing {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn try_parse_complete_or_error() {
+        assert_eq!(
+            try_parse_tokens(lex_input("(a)").unwrap()),
+            Ok(ParseOutcome::Complete(Expr::List(vec![Expr::Symbol(
+                "a".to_string()
+            )])))
+        );
+        assert_eq!(
+            try_parse_tokens(lex_input("(a))").unwrap()),
+            Err("Unexpected input after expression")
+        );
+        assert_eq!(
+            try_parse_tokens(lex_input(")").unwrap()),
+            Err("Unexpected closing bracket")
+        );
+        assert_eq!(
+            try_parse_tokens(lex_input("(a . b c").unwrap()),
+            Err("Dotted list must have exactly one item after the dot")
+        );
+    }
+
+    #[test]
+    fn try_parse_program_forms() {
+        let tests = vec![
+            ("(a) (b)", Ok(ParseOutcome::Complete(vec!["(a)", "(b)"]))),
+            ("1 2", Ok(ParseOutcome::Complete(vec!["1", "2"]))),
+            ("#;a", Ok(ParseOutcome::Complete(vec![]))),
+            ("(a) (b", Ok(ParseOutcome::Incomplete)),
+            ("(a) '", Ok(ParseOutcome::Incomplete)),
+            ("(a) #;", Ok(ParseOutcome::Incomplete)),
+            ("(a)) (b", Err("Unexpected closing bracket")),
+            (
+                "(. a) (b",
+                Err("Dotted list must have at least one item before the dot"),
+            ),
+        ];
+
+        for (input, expect) in tests {
+            let expected_output = expect.map(|outcome| match outcome {
+                ParseOutcome::Complete(forms) => ParseOutcome::Complete(
+                    forms
+                        .into_iter()
+                        .map(|form| parse_tokens(lex_input(form).unwrap()).unwrap())
+                        .collect::<Vec<Expr>>(),
+                ),
+                ParseOutcome::Incomplete => ParseOutcome::Incomplete,
+            });
+
+            assert_eq!(
+                try_parse_program(lex_input_with_positions(input, false).unwrap()),
+                expected_output,
+                "parsing {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn parse_program_forms() {
+        let input = "(define x 1) 'x #;(ignored) x #(1\n2) #;y";
+
+        let expected_output = vec!["(define x 1)", "'x", "x", "#(1 2)"]
+            .into_iter()
+            .map(|expect| parse_tokens(lex_input(expect).unwrap()).unwrap())
+            .collect::<Vec<Expr>>();
+
+        assert_eq!(
+            parse_program(lex_input_with_positions(input, false).unwrap()),
+            Ok(expected_output)
+        );
+    }
+
+    #[test]
+    fn parse_program_with_nested_datum_comments() {
+        let input = "(a #;b c) (d)\n(define (f x)\n  #;(debug x)\n  x) (f 1)";
+
+        let expected_output = vec!["(a c)", "(d)", "(define (f x) x)", "(f 1)"]
+            .into_iter()
+            .map(|expect| parse_tokens(lex_input(expect).unwrap()).unwrap())
+            .collect::<Vec<Expr>>();
+
+        assert_eq!(
+            parse_program(lex_input_with_positions(input, false).unwrap()),
+            Ok(expected_output)
+        );
+    }
+
+    #[test]
+    fn parse_program_with_brackets_in_first_column() {
+        let input = "(define table\n'((1 2)\n(3 4)\n#(5)))\n(define x\n(car table))";
+
+        let expected_output = vec![
+            "(define table '((1 2) (3 4) #(5)))",
+            "(define x (car table))",
+        ]
+        .into_iter()
+        .map(|expect| parse_tokens(lex_input(expect).unwrap()).unwrap())
+        .collect::<Vec<Expr>>();
+
+        assert_eq!(
+            parse_program(lex_input_with_positions(input, false).unwrap()),
+            Ok(expected_output)
+        );
+    }
+
+    #[test]
+    fn parse_program_only_recovers_from_missing_brackets() {
+        let input = "(define table\n'((1 2)\n(3 4 . )))";
+
+        let expected_output = vec![ParseError {
+            position: Position { line: 1, column: 1 },
+            message: "Dotted list must have an item after the dot",
+        }];
+
+        assert_eq!(
+            parse_program(lex_input_with_positions(input, false).unwrap()),
+            Err(expected_output)
+        );
+    }
+
+    #[test]
+    fn parse_program_reports_every_error() {
+        let input = r#"
+(h))
+(define (f x)
+  (+ x 1)
+(define y (. 2))
+(g x)
+#;
+"#;
+
+        let expected_output = vec![
+            ParseError {
+                position: Position { line: 2, column: 4 },
+                message: "Unexpected closing bracket",
+            },
+            ParseError {
+                position: Position { line: 3, column: 1 },
+                message: "Missing closing bracket",
+            },
+            ParseError {
+                position: Position { line: 5, column: 1 },
+                message: "Dotted list must have at least one item before the dot",
+            },
+            ParseError {
+                position: Position { line: 7, column: 1 },
+                message: "Unexpected end of input",
+            },
+        ];
+
+        assert_eq!(
+            parse_program(lex_input_with_positions(input, false).unwrap()),
+            Err(expected_output)
+        );
+    }
+
+    fn compare(input: &str, expected_output: Expr) {
+        let actual_output = parse_tokens(lex_input(input).unwrap()).unwrap();
+
+        assert_eq!(actual_output, expected_output);
+    }
 }
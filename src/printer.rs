@@ -0,0 +1,148 @@
+use crate::lexer::{self, LexToken};
+use crate::parser::Expr;
+
+pub fn print_expr(input: &Expr) -> String {
+    match input {
+        Expr::Num(num) => num.to_string(),
+        Expr::Bool(true) => "#t".to_string(),
+        Expr::Bool(false) => "#f".to_string(),
+        Expr::Char(char) => print_char(*char),
+        Expr::Symbol(symbol) => print_symbol(symbol),
+        Expr::String(string) => print_string(string),
+        Expr::List(items) => format!("({})", print_items(items)),
+        Expr::Vector(items) => format!("#({})", print_items(items)),
+        Expr::Bytevector(bytes) => format!(
+            "#u8({})",
+            bytes
+                .iter()
+                .map(|byte| byte.to_string())
+                .collect::<Vec<String>>()
+                .join(" ")
+        ),
+        Expr::DottedList(items, tail) => {
+            format!("({} . {})", print_items(items), print_expr(tail))
+        }
+    }
+}
+
+fn print_items(items: &[Expr]) -> String {
+    items
+        .iter()
+        .map(print_expr)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn print_char(char: char) -> String {
+    let name = match char {
+        '\u{7}' => "alarm",
+        '\u{8}' => "backspace",
+        '\u{7f}' => "delete",
+        '\u{1b}' => "escape",
+        '\n' => "newline",
+        '\0' => "null",
+        '\r' => "return",
+        ' ' => "space",
+        '\t' => "tab",
+        _ => return format!("#\\{}", char),
+    };
+
+    format!("#\\{}", name)
+}
+
+fn print_string(string: &str) -> String {
+    format!("\"{}\"", escape(string, '"'))
+}
+
+fn print_symbol(symbol: &str) -> String {
+    if symbol_needs_pipes(symbol) {
+        return format!("|{}|", escape(symbol, '|'));
+    }
+
+    symbol.to_string()
+}
+
+/// A symbol needs pipes if writing it bare would read back as something
+/// other than the same symbol. It is read as if folding case, so symbols
+/// that only differ from another by case survive a fold-case reader too.
+fn symbol_needs_pipes(symbol: &str) -> bool {
+    match lexer::lex_input_folding_case(symbol).as_deref() {
+        Ok([LexToken::Symbol(lexed)]) => lexed != symbol,
+        _ => true,
+    }
+}
+
+fn escape(input: &str, delimiter: char) -> String {
+    let mut output = String::from("");
+
+    for char in input.chars() {
+        if char == delimiter || char == '\\' {
+            output.push('\\');
+        }
+
+        output.push(char);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::*;
+    use crate::parser::*;
+
+    #[test]
+    fn print_symbol() {
+        let tests = vec![
+            ("little-schemer", "little-schemer"),
+            ("little schemer", "|little schemer|"),
+            ("little\u{a0}schemer", "|little\u{a0}schemer|"),
+            ("(", "|(|"),
+            ("", "||"),
+            ("123", "|123|"),
+            ("1+", "|1+|"),
+            ("-5x", "|-5x|"),
+            (".5a", "|.5a|"),
+            ("1e5x", "|1e5x|"),
+            ("-", "-"),
+            ("Foo", "|Foo|"),
+            ("#!fold-case", "|#!fold-case|"),
+            (".", "|.|"),
+            ("#t", "|#t|"),
+            ("a|b", "a|b"),
+            ("|a", r#"|\|a|"#),
+            (r#"a\b c"#, r#"|a\\b c|"#),
+        ];
+
+        for (input, expect) in tests {
+            let symbol = Expr::Symbol(input.to_string());
+
+            assert_eq!(print_expr(&symbol), expect);
+            assert_eq!(round_trip(expect), symbol);
+            assert_eq!(
+                parse_tokens(lex_input_folding_case(expect).unwrap()).unwrap(),
+                symbol
+            );
+        }
+    }
+
+    #[test]
+    fn print_round_trip() {
+        let tests = vec![
+            "(define (add a b) (+ a b))",
+            "(lambda (a b . rest) rest)",
+            r#"(quote (1 0.5 -2 #t #f #\a #\space "a \"quoted\" \\ string"))"#,
+            "#(1 #(2) ())",
+            "#u8(0 255)",
+        ];
+
+        for input in tests {
+            assert_eq!(print_expr(&round_trip(input)), input);
+        }
+    }
+
+    fn round_trip(input: &str) -> Expr {
+        parse_tokens(lex_input(input).unwrap()).unwrap()
+    }
+}